@@ -0,0 +1,119 @@
+use std::fmt::{self, Display};
+
+/// Describes why a requested project, module or target name cannot be used,
+/// or why a changeset could not be prepared for some other recoverable
+/// reason (e.g. an unreadable source directory).
+#[derive(Debug, PartialEq, Clone)]
+pub enum NameError {
+    Empty,
+    ContainsWhitespace(String),
+    ContainsPathSeparator(String),
+    StartsWithDigit(String),
+    IllegalCharacter(String, char),
+    Io(String),
+}
+
+impl Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameError::Empty => write!(f, "name must not be empty"),
+            NameError::ContainsWhitespace(name) => {
+                write!(f, "name \"{}\" must not contain whitespace", name)
+            }
+            NameError::ContainsPathSeparator(name) => {
+                write!(f, "name \"{}\" must not contain a path separator", name)
+            }
+            NameError::StartsWithDigit(name) => {
+                write!(f, "name \"{}\" must not start with a digit", name)
+            }
+            NameError::IllegalCharacter(name, character) => write!(
+                f,
+                "name \"{}\" contains the illegal character '{}'",
+                name, character
+            ),
+            NameError::Io(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Validate that `name` is a legal C++/UE module or project identifier.
+///
+/// The rename workflows also derive an `_API` export macro from the
+/// uppercased name, but that doesn't need a separate check here: every
+/// character this function accepts is ASCII alphanumeric or `_`, and
+/// `char::to_uppercase()` never maps a character in that set outside of it,
+/// so the uppercased form is always legal whenever `name` is.
+pub fn validate_name(name: &str) -> Result<(), NameError> {
+    if name.is_empty() {
+        return Err(NameError::Empty);
+    }
+
+    if name.chars().any(char::is_whitespace) {
+        return Err(NameError::ContainsWhitespace(name.to_string()));
+    }
+
+    if name.contains('/') || name.contains('\\') {
+        return Err(NameError::ContainsPathSeparator(name.to_string()));
+    }
+
+    if name.chars().next().map_or(false, |c| c.is_ascii_digit()) {
+        return Err(NameError::StartsWithDigit(name.to_string()));
+    }
+
+    if let Some(illegal) = name.chars().find(|c| !is_identifier_char(*c)) {
+        return Err(NameError::IllegalCharacter(name.to_string(), illegal));
+    }
+
+    Ok(())
+}
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_empty_name() {
+        assert_eq!(validate_name(""), Err(NameError::Empty));
+    }
+
+    #[test]
+    fn rejects_whitespace() {
+        assert_eq!(
+            validate_name("My Project"),
+            Err(NameError::ContainsWhitespace("My Project".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_path_separators() {
+        assert_eq!(
+            validate_name("Sub/Project"),
+            Err(NameError::ContainsPathSeparator("Sub/Project".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_leading_digit() {
+        assert_eq!(
+            validate_name("1Project"),
+            Err(NameError::StartsWithDigit("1Project".into()))
+        );
+    }
+
+    #[test]
+    fn rejects_illegal_characters() {
+        assert_eq!(
+            validate_name("Project-Name"),
+            Err(NameError::IllegalCharacter("Project-Name".into(), '-'))
+        );
+    }
+
+    #[test]
+    fn accepts_legal_identifier() {
+        assert_eq!(validate_name("MyProject_2"), Ok(()));
+    }
+}
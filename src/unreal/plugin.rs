@@ -0,0 +1,212 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use regex::Regex;
+use walkdir::WalkDir;
+
+use crate::changes::{Change, ReplaceInFile};
+
+/// Find every `.uplugin` descriptor under `project_root/Plugins` and
+/// return the `Source` directory beside it, so module discovery and
+/// dependency scanning cover plugin modules as well as
+/// `project_root/Source`.
+pub fn find_plugin_source_roots(project_root: &Path) -> Vec<PathBuf> {
+    WalkDir::new(project_root.join("Plugins"))
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "uplugin"))
+        .filter_map(|descriptor| descriptor.parent().map(|root| root.join("Source")))
+        .filter(|source| source.is_dir())
+        .collect()
+}
+
+/// Emit a `ReplaceInFile` change for every `.Build.cs` file under any of
+/// `module_roots` that lists `old_name` inside
+/// `PublicDependencyModuleNames`/`PrivateDependencyModuleNames`, so
+/// renaming a module that other modules depend on doesn't break their
+/// build rules.
+pub fn find_build_file_dependents(
+    module_roots: &[PathBuf],
+    old_name: &str,
+    new_name: &str,
+) -> Vec<Change> {
+    let dependency_array = Regex::new(&format!(
+        r#"(?s)(Public|Private)DependencyModuleNames\.AddRange\(new string\[\]\s*\{{[^}}]*?"{}"[^}}]*?\}}\s*\)"#,
+        regex::escape(old_name)
+    ))
+    .unwrap();
+
+    module_roots
+        .iter()
+        .flat_map(|root| WalkDir::new(root).into_iter())
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .map_or(false, |name| name.ends_with(".Build.cs"))
+        })
+        .filter_map(|path| fs::read_to_string(&path).ok().map(|content| (path, content)))
+        .filter(|(_, content)| dependency_array.is_match(content))
+        .map(|(path, _)| {
+            Change::ReplaceInFile(ReplaceInFile::new(
+                path,
+                format!(r#""{}""#, old_name),
+                format!(r#""{}""#, new_name),
+            ))
+        })
+        .collect()
+}
+
+/// Emit a `ReplaceInFile` change for every plugin descriptor whose
+/// `"Modules"` array names `old_name`, so a module rename also updates the
+/// descriptor that declares it. The project's own `.uproject` descriptor
+/// is not handled here: `replace_mod_reference_in_project_descriptor`
+/// already rewrites the module name there.
+pub fn find_descriptor_module_entries(
+    project_root: &Path,
+    old_name: &str,
+    new_name: &str,
+) -> Vec<Change> {
+    let module_entry = Regex::new(&format!(
+        r#""Name"(?P<sep>\s*:\s*)"{}""#,
+        regex::escape(old_name)
+    ))
+    .unwrap();
+
+    let descriptors = WalkDir::new(project_root.join("Plugins"))
+        .max_depth(2)
+        .into_iter()
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "uplugin"));
+
+    descriptors
+        .filter_map(|path| fs::read_to_string(&path).ok().map(|content| (path, content)))
+        .filter(|(_, content)| module_entry.is_match(content))
+        .map(|(path, _)| {
+            Change::ReplaceInFile(ReplaceInFile::new(
+                path,
+                module_entry.as_str(),
+                format!(r#""Name"$sep"{}""#, new_name),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::changes::Change;
+
+    use super::{find_build_file_dependents, find_descriptor_module_entries, find_plugin_source_roots};
+
+    fn fixture_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("plugin_test_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn discovers_the_source_directory_of_a_plugin_descriptor() {
+        let root = fixture_dir("source_roots");
+        let plugin_root = root.join("Plugins/MyPlugin");
+        fs::create_dir_all(plugin_root.join("Source")).unwrap();
+        fs::write(plugin_root.join("MyPlugin.uplugin"), "{}\n").unwrap();
+
+        let roots = find_plugin_source_roots(&root);
+
+        assert_eq!(roots, vec![plugin_root.join("Source")]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn ignores_a_plugin_descriptor_with_no_source_directory() {
+        let root = fixture_dir("no_source_dir");
+        let plugin_root = root.join("Plugins/MyPlugin");
+        fs::create_dir_all(&plugin_root).unwrap();
+        fs::write(plugin_root.join("MyPlugin.uplugin"), "{}\n").unwrap();
+
+        let roots = find_plugin_source_roots(&root);
+
+        assert_eq!(roots, vec![]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rewrites_a_build_file_dependent_listed_in_a_dependency_array() {
+        let root = fixture_dir("build_file_dependent");
+        fs::create_dir_all(root.join("Dependent")).unwrap();
+        fs::write(
+            root.join("Dependent/Dependent.Build.cs"),
+            "PublicDependencyModuleNames.AddRange(new string[] {\n\t\"Core\",\n\t\"Start\"\n});\n",
+        )
+        .unwrap();
+
+        let changes = find_build_file_dependents(&[root.clone()], "Start", "Finish");
+        let replace = match &changes[0] {
+            Change::ReplaceInFile(replace) => replace,
+            other => panic!("expected ReplaceInFile, got {:?}", other),
+        };
+
+        let regex = regex::Regex::new(&replace.pattern).unwrap();
+        let content = fs::read_to_string(root.join("Dependent/Dependent.Build.cs")).unwrap();
+        let rewritten = regex.replace(&content, replace.replacement.as_str());
+
+        assert!(rewritten.contains("\"Finish\""));
+        assert!(!rewritten.contains("\"Start\""));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn ignores_a_build_file_where_the_name_appears_outside_a_dependency_array() {
+        let root = fixture_dir("build_file_unrelated");
+        fs::create_dir_all(root.join("Dependent")).unwrap();
+        fs::write(
+            root.join("Dependent/Dependent.Build.cs"),
+            "// built for Start, but not a dependency\nPublicDependencyModuleNames.AddRange(new string[] { \"Core\" });\n",
+        )
+        .unwrap();
+
+        let changes = find_build_file_dependents(&[root.clone()], "Start", "Finish");
+
+        assert_eq!(changes, vec![]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rewrites_a_descriptor_module_entry_preserving_separator_whitespace() {
+        let root = fixture_dir("descriptor_module_entry");
+        fs::create_dir_all(root.join("Plugins/MyPlugin")).unwrap();
+        fs::write(
+            root.join("Plugins/MyPlugin/MyPlugin.uplugin"),
+            "{\n\t\"Modules\": [\n\t\t{ \"Name\"  :  \"Start\" }\n\t]\n}\n",
+        )
+        .unwrap();
+
+        let changes = find_descriptor_module_entries(&root, "Start", "Finish");
+        let replace = match &changes[0] {
+            Change::ReplaceInFile(replace) => replace,
+            other => panic!("expected ReplaceInFile, got {:?}", other),
+        };
+
+        let regex = regex::Regex::new(&replace.pattern).unwrap();
+        let content =
+            fs::read_to_string(root.join("Plugins/MyPlugin/MyPlugin.uplugin")).unwrap();
+        let rewritten = regex.replace(&content, replace.replacement.as_str());
+
+        assert!(rewritten.contains("\"Name\"  :  \"Finish\""));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
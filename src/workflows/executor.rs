@@ -0,0 +1,245 @@
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    changes::{AppendIniEntry, Change, RenameFile, ReplaceInFile, SetIniEntry},
+    presentation::log,
+};
+
+/// Describes why a changeset could not be executed.
+#[derive(Debug, Clone)]
+pub enum ExecutorError {
+    DuplicateSource(PathBuf),
+    DuplicateDestination(PathBuf),
+    DestinationExists(PathBuf),
+    UnreadablePath(PathBuf, String),
+    ApplyFailed(String),
+}
+
+impl Display for ExecutorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExecutorError::DuplicateSource(path) => {
+                write!(f, "two changes both rename from {}", path.display())
+            }
+            ExecutorError::DuplicateDestination(path) => {
+                write!(f, "two changes both rename to {}", path.display())
+            }
+            ExecutorError::DestinationExists(path) => {
+                write!(f, "rename destination already exists: {}", path.display())
+            }
+            ExecutorError::UnreadablePath(path, message) => {
+                write!(f, "could not read {}: {}", path.display(), message)
+            }
+            ExecutorError::ApplyFailed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// One recorded action, kept so it can be reversed if a later step fails.
+enum Undo {
+    RenameFile { from: PathBuf, to: PathBuf },
+    RestoreFile { path: PathBuf, original: Vec<u8> },
+}
+
+/// Apply `changeset` to the project in two phases.
+///
+/// Phase one validates the entire changeset without writing anything:
+/// every `RenameFile` source/destination is canonicalized so a missing
+/// source, or a duplicate or already-occupied destination, is caught up
+/// front, and every
+/// `ReplaceInFile`/`SetIniEntry`/`AppendIniEntry` path is checked to be
+/// readable. Phase two then applies each change in order, recording an
+/// undo entry for it (the inverse rename, or the original file bytes). If
+/// any step in phase two fails, the undo log is replayed in reverse to
+/// restore the project to its pre-run state, and the failing change is
+/// reported through [`log`].
+pub fn execute(changeset: &[Change]) -> Result<(), ExecutorError> {
+    validate(changeset)?;
+
+    let mut undo_log = vec![];
+
+    for change in changeset {
+        if let Err(err) = apply(change, &mut undo_log) {
+            log::basic(format!("failed to apply change \"{}\": {}", change, err));
+            rollback(&undo_log);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+fn validate(changeset: &[Change]) -> Result<(), ExecutorError> {
+    let mut sources = HashSet::new();
+    let mut destinations = HashSet::new();
+
+    for change in changeset {
+        match change {
+            Change::RenameFile(RenameFile { from, to }) => {
+                let source = fs::canonicalize(from)
+                    .map_err(|err| ExecutorError::UnreadablePath(from.clone(), err.to_string()))?;
+
+                if !sources.insert(source) {
+                    return Err(ExecutorError::DuplicateSource(from.clone()));
+                }
+
+                let destination = normalize_destination(to);
+
+                if !destinations.insert(destination.clone()) {
+                    return Err(ExecutorError::DuplicateDestination(destination));
+                }
+
+                if destination.exists() {
+                    return Err(ExecutorError::DestinationExists(destination));
+                }
+            }
+            Change::ReplaceInFile(ReplaceInFile { path, .. }) => verify_readable(path)?,
+            Change::SetIniEntry(SetIniEntry { path, .. }) => verify_readable(path)?,
+            Change::AppendIniEntry(AppendIniEntry { path, .. }) => verify_readable(path)?,
+        }
+    }
+
+    Ok(())
+}
+
+/// `to` usually doesn't exist yet, so it can't be `fs::canonicalize`d
+/// directly; canonicalize its parent instead and rejoin the file name.
+fn normalize_destination(to: &Path) -> PathBuf {
+    match (to.parent().and_then(|parent| fs::canonicalize(parent).ok()), to.file_name()) {
+        (Some(parent), Some(name)) => parent.join(name),
+        _ => to.to_path_buf(),
+    }
+}
+
+fn verify_readable(path: &Path) -> Result<(), ExecutorError> {
+    fs::File::open(path)
+        .map(|_| ())
+        .map_err(|err| ExecutorError::UnreadablePath(path.to_path_buf(), err.to_string()))
+}
+
+fn apply(change: &Change, undo_log: &mut Vec<Undo>) -> Result<(), ExecutorError> {
+    if let Change::RenameFile(RenameFile { from, to }) = change {
+        fs::rename(from, to).map_err(|err| ExecutorError::ApplyFailed(err.to_string()))?;
+        undo_log.push(Undo::RenameFile {
+            from: from.clone(),
+            to: to.clone(),
+        });
+        return Ok(());
+    }
+
+    let path = mutated_path(change).to_path_buf();
+    let original =
+        fs::read(&path).map_err(|err| ExecutorError::ApplyFailed(err.to_string()))?;
+
+    change
+        .apply()
+        .map_err(|err| ExecutorError::ApplyFailed(err.to_string()))?;
+
+    undo_log.push(Undo::RestoreFile { path, original });
+
+    Ok(())
+}
+
+fn mutated_path(change: &Change) -> &Path {
+    match change {
+        Change::ReplaceInFile(ReplaceInFile { path, .. }) => path,
+        Change::SetIniEntry(SetIniEntry { path, .. }) => path,
+        Change::AppendIniEntry(AppendIniEntry { path, .. }) => path,
+        Change::RenameFile(_) => unreachable!("RenameFile is handled separately in apply()"),
+    }
+}
+
+fn rollback(undo_log: &[Undo]) {
+    for undo in undo_log.iter().rev() {
+        match undo {
+            Undo::RenameFile { from, to } => {
+                if let Err(err) = fs::rename(to, from) {
+                    log::basic(format!(
+                        "rollback failed to restore {}: {}",
+                        from.display(),
+                        err
+                    ));
+                }
+            }
+            Undo::RestoreFile { path, original } => {
+                if let Err(err) = fs::write(path, original) {
+                    log::basic(format!(
+                        "rollback failed to restore {}: {}",
+                        path.display(),
+                        err
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::changes::{Change, RenameFile, ReplaceInFile};
+
+    use super::{execute, ExecutorError};
+
+    fn fixture_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("executor_test_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn rejects_a_rename_whose_source_does_not_exist() {
+        let root = fixture_dir("missing_source");
+
+        let result = execute(&[Change::RenameFile(RenameFile::new(
+            root.join("Missing.h"),
+            root.join("Renamed.h"),
+        ))]);
+
+        assert!(matches!(result, Err(ExecutorError::UnreadablePath(..))));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rejects_two_renames_sharing_the_same_source() {
+        let root = fixture_dir("duplicate_source");
+        fs::write(root.join("Start.h"), "content").unwrap();
+
+        let result = execute(&[
+            Change::RenameFile(RenameFile::new(root.join("Start.h"), root.join("Finish.h"))),
+            Change::RenameFile(RenameFile::new(root.join("Start.h"), root.join("Other.h"))),
+        ]);
+
+        assert!(matches!(result, Err(ExecutorError::DuplicateSource(..))));
+        assert!(root.join("Start.h").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rejects_the_whole_changeset_without_writing_anything_when_one_path_is_unreadable() {
+        let root = fixture_dir("validate_first");
+        fs::write(root.join("Start.h"), "class Start {};").unwrap();
+
+        let result = execute(&[
+            Change::ReplaceInFile(ReplaceInFile::new(root.join("Start.h"), "Start", "Finish")),
+            Change::ReplaceInFile(ReplaceInFile::new(root.join("Missing.h"), "Start", "Finish")),
+        ]);
+
+        assert!(matches!(result, Err(ExecutorError::UnreadablePath(..))));
+        assert_eq!(
+            fs::read_to_string(root.join("Start.h")).unwrap(),
+            "class Start {};"
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,176 @@
+use std::fs;
+
+use regex::Regex;
+
+use crate::{
+    changes::{AppendIniEntry, Change, RenameFile, ReplaceInFile, SetIniEntry},
+    presentation::log,
+};
+
+/// Render `changeset` as a diff preview without touching disk, naming the
+/// file each change touches so a user can audit every edit a rename would
+/// make. Changes are printed in changeset order; several `ReplaceInFile`
+/// changes to the same file each get their own file heading and are not
+/// merged into one group. Backs the
+/// `--dry-run` flag: instead of applying the changeset, the caller passes
+/// it here.
+///
+/// `ReplaceInFile` entries are evaluated against the real file contents so
+/// the preview shows the concrete before/after match, not just the pattern;
+/// patterns are matched against the whole file rather than line by line, so
+/// multi-line patterns (e.g. `(?s)`-flagged ones spanning several lines)
+/// still report correctly. A pattern that matches zero times is flagged as
+/// a warning, since it usually means the expected content was not found.
+pub fn preview(changeset: &[Change]) {
+    for change in changeset {
+        match change {
+            Change::ReplaceInFile(replace) => preview_replace_in_file(replace),
+            Change::RenameFile(rename) => preview_rename_file(rename),
+            Change::SetIniEntry(set) => preview_set_ini_entry(set),
+            Change::AppendIniEntry(append) => preview_append_ini_entry(append),
+        }
+    }
+}
+
+fn preview_replace_in_file(change: &ReplaceInFile) {
+    log::basic(format!("{}:", change.path.display()));
+
+    let content = match fs::read_to_string(&change.path) {
+        Ok(content) => content,
+        Err(err) => {
+            log::basic(format!("  could not read file: {}", err));
+            return;
+        }
+    };
+
+    let regex = match Regex::new(&change.pattern) {
+        Ok(regex) => regex,
+        Err(err) => {
+            log::basic(format!("  invalid pattern \"{}\": {}", change.pattern, err));
+            return;
+        }
+    };
+
+    let matches = find_matches(&content, &regex, &change.replacement);
+
+    if matches.is_empty() {
+        log::basic(format!(
+            "  warning: pattern \"{}\" was not found in this file",
+            change.pattern
+        ));
+        return;
+    }
+
+    for found in matches {
+        log::basic(format!("  {} - {}", found.line, found.before));
+        log::basic(format!("  {} + {}", found.line, found.after));
+    }
+}
+
+/// One matched occurrence of a `ReplaceInFile` pattern within a file.
+struct Match {
+    /// 1-based line the match starts on.
+    line: usize,
+    before: String,
+    after: String,
+}
+
+/// Match `regex` against the whole of `content` (not line by line, so
+/// multi-line patterns are found) and expand `replacement` against each
+/// match, reporting the 1-based line each one starts on.
+fn find_matches(content: &str, regex: &Regex, replacement: &str) -> Vec<Match> {
+    regex
+        .captures_iter(content)
+        .map(|captures| {
+            let whole = captures.get(0).unwrap();
+            let line = content[..whole.start()].matches('\n').count() + 1;
+            let mut after = String::new();
+            captures.expand(replacement, &mut after);
+            Match {
+                line,
+                before: whole.as_str().to_string(),
+                after,
+            }
+        })
+        .collect()
+}
+
+fn preview_rename_file(change: &RenameFile) {
+    log::basic(format!(
+        "{} -> {}",
+        change.from.display(),
+        change.to.display()
+    ));
+}
+
+fn preview_set_ini_entry(change: &SetIniEntry) {
+    log::basic(format!(
+        "{}: set [{}] {} = {}",
+        change.path.display(),
+        change.section,
+        change.key,
+        change.value
+    ));
+}
+
+fn preview_append_ini_entry(change: &AppendIniEntry) {
+    log::basic(format!(
+        "{}: append to [{}] {} = {}",
+        change.path.display(),
+        change.section,
+        change.key,
+        change.value
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::find_matches;
+
+    #[test]
+    fn reports_the_line_a_single_line_match_starts_on() {
+        let content = "first\nGameDisplayName=Start\nthird\n";
+        let regex = Regex::new(r"\bStart\b").unwrap();
+
+        let matches = find_matches(content, &regex, "Finish");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 2);
+        assert_eq!(matches[0].before, "Start");
+        assert_eq!(matches[0].after, "Finish");
+    }
+
+    #[test]
+    fn finds_a_pattern_that_spans_multiple_lines() {
+        let content = "before\nDependencyModuleNames.AddRange(new string[] {\n\t\"Start\"\n});\nafter\n";
+        let regex = Regex::new(
+            r#"(?s)DependencyModuleNames\.AddRange\(new string\[\]\s*\{[^}]*?"Start"[^}]*?\}\s*\)"#,
+        )
+        .unwrap();
+
+        let matches = find_matches(content, &regex, "DependencyModuleNames.AddRange(new string[] { \"Finish\" })");
+
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].line, 2);
+        assert!(matches[0].before.contains("\"Start\""));
+    }
+
+    #[test]
+    fn expands_named_capture_groups_in_the_replacement() {
+        let content = r#"#include "Sub/Start.h""#;
+        let regex = Regex::new(r#"#include\s+"(?P<dir>(?:.*/)?)Start\.h""#).unwrap();
+
+        let matches = find_matches(content, &regex, r#"#include "${dir}Finish.h""#);
+
+        assert_eq!(matches[0].after, r#"#include "Sub/Finish.h""#);
+    }
+
+    #[test]
+    fn returns_no_matches_when_the_pattern_is_absent() {
+        let matches = find_matches("nothing here", &Regex::new(r"\bStart\b").unwrap(), "Finish");
+
+        assert!(matches.is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+use std::{collections::HashSet, fs, path::Path};
+
+use regex::Regex;
+use walkdir::WalkDir;
+
+use crate::changes::{Change, ReplaceInFile};
+
+/// File extensions worth scanning for stray references. Binary assets and
+/// generated files (`.sln`, `Intermediate/`, `Binaries/`) are not walked.
+const SCANNED_EXTENSIONS: [&str; 5] = ["h", "cpp", "cs", "ini", "uproject"];
+
+/// Walk `project_root` and turn every occurrence of `old_name` into a
+/// `ReplaceInFile` change, so a rename also catches references a hardcoded
+/// changeset has no way of knowing about ahead of time (non-default file
+/// layouts, stray mentions in config files, etc).
+///
+/// `old_name` is matched as a whole word (`\bStart\b`) to avoid clobbering
+/// names that merely contain it, e.g. `StartupHelper`. Any `#include
+/// "OldName.h"` directive is additionally rewritten to point at the new
+/// header, even when the include path carries a subdirectory prefix.
+///
+/// A file is skipped entirely if `already_covered` (the changeset built so
+/// far by the caller's targeted, hardcoded changes) already holds a
+/// `ReplaceInFile` for it whose pattern is `old_name` itself, with no
+/// surrounding quotes or other characters — e.g. the project descriptor or
+/// module build class rewrite. Such a bare pattern rewrites every
+/// occurrence of `old_name` in the file, quoted or not, so there is nothing
+/// left for the generic scan below to find there.
+///
+/// A pattern that only matches `old_name` quoted (as used by the plugin
+/// dependency-array and descriptor-entry rewrites) is deliberately NOT
+/// treated as full coverage, even though it looks similar: it only rewrites
+/// quoted occurrences, so a bare, unquoted stray reference elsewhere in the
+/// same file (a comment, say) would otherwise be silently dropped. Such
+/// files are still scanned, at the cost of a harmless duplicate match on
+/// the quoted occurrence those targeted changes already handle.
+pub fn scan_references(
+    project_root: &Path,
+    old_name: &str,
+    new_name: &str,
+    already_covered: &[Change],
+) -> Vec<Change> {
+    if !project_root.is_dir() {
+        return vec![];
+    }
+
+    let word_boundary = word_boundary_regex(old_name);
+    let include_directive = include_directive_regex(old_name);
+    let covered_paths: HashSet<_> = already_covered
+        .iter()
+        .filter_map(|change| fully_rewrites_old_name(change, old_name))
+        .collect();
+
+    WalkDir::new(project_root)
+        .into_iter()
+        .filter_entry(|entry| {
+            entry.depth() == 0
+                || !matches!(entry.file_name().to_str(), Some("Intermediate") | Some("Binaries"))
+        })
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().to_owned())
+        .filter(|path| path.is_file() && has_scanned_extension(path))
+        .filter(|path| !covered_paths.contains(path.as_path()))
+        .filter_map(|path| fs::read_to_string(&path).ok().map(|content| (path, content)))
+        .flat_map(|(path, content)| {
+            let mut changes = vec![];
+
+            if include_directive.is_match(&content) {
+                changes.push(Change::ReplaceInFile(ReplaceInFile::new(
+                    &path,
+                    include_directive.as_str(),
+                    format!(r#"#include "${{dir}}{}.h""#, new_name),
+                )));
+            }
+
+            if word_boundary.is_match(&content) {
+                changes.push(Change::ReplaceInFile(ReplaceInFile::new(
+                    &path,
+                    word_boundary.as_str(),
+                    new_name,
+                )));
+            }
+
+            changes
+        })
+        .collect()
+}
+
+fn word_boundary_regex(name: &str) -> Regex {
+    Regex::new(&format!(r"\b{}\b", regex::escape(name))).unwrap()
+}
+
+fn include_directive_regex(header_name: &str) -> Regex {
+    Regex::new(&format!(
+        r#"#include\s+"(?P<dir>(?:.*/)?){}\.h""#,
+        regex::escape(header_name)
+    ))
+    .unwrap()
+}
+
+fn has_scanned_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| SCANNED_EXTENSIONS.contains(&ext))
+}
+
+/// Returns `change`'s path if it is a `ReplaceInFile` whose pattern is
+/// exactly `old_name`, i.e. it rewrites every occurrence (quoted or bare)
+/// of `old_name` anywhere in the file.
+fn fully_rewrites_old_name<'a>(change: &'a Change, old_name: &str) -> Option<&'a Path> {
+    match change {
+        Change::ReplaceInFile(ReplaceInFile { path, pattern, .. }) if pattern == old_name => {
+            Some(path)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::changes::{Change, ReplaceInFile};
+
+    use super::scan_references;
+
+    fn fixture_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("reference_scanner_test_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn finds_bare_word_occurrences_outside_the_hardcoded_file_list() {
+        let root = fixture_dir("bare_word");
+        fs::write(root.join("Notes.ini"), "GameDisplayName=Start\n").unwrap();
+
+        let changes = scan_references(&root, "Start", "Finish", &[]);
+
+        assert_eq!(
+            changes,
+            vec![Change::ReplaceInFile(ReplaceInFile::new(
+                root.join("Notes.ini"),
+                r"\bStart\b",
+                "Finish",
+            ))]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn rewrites_include_directive_preserving_the_directory_prefix() {
+        let root = fixture_dir("include_directive");
+        fs::write(root.join("Consumer.cpp"), "#include \"Sub/Start.h\"\n").unwrap();
+
+        let changes = scan_references(&root, "Start", "Finish", &[]);
+        let replace = match &changes[0] {
+            Change::ReplaceInFile(replace) => replace,
+            other => panic!("expected ReplaceInFile, got {:?}", other),
+        };
+
+        let regex = regex::Regex::new(&replace.pattern).unwrap();
+        let rewritten = regex.replace("#include \"Sub/Start.h\"", replace.replacement.as_str());
+
+        assert_eq!(rewritten, "#include \"Sub/Finish.h\"");
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn ignores_names_that_only_appear_as_a_substring() {
+        let root = fixture_dir("substring");
+        fs::write(root.join("Notes.ini"), "GameDisplayName=StartupHelper\n").unwrap();
+
+        let changes = scan_references(&root, "Start", "Finish", &[]);
+
+        assert_eq!(changes, vec![]);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn skips_a_file_already_touched_by_an_earlier_targeted_change() {
+        let root = fixture_dir("already_covered");
+        fs::write(root.join("Start.Build.cs"), "// references Start\n").unwrap();
+        fs::write(root.join("Notes.ini"), "GameDisplayName=Start\n").unwrap();
+
+        let already_covered = vec![Change::ReplaceInFile(ReplaceInFile::new(
+            root.join("Start.Build.cs"),
+            "Start",
+            "Finish",
+        ))];
+
+        let changes = scan_references(&root, "Start", "Finish", &already_covered);
+
+        assert_eq!(
+            changes,
+            vec![Change::ReplaceInFile(ReplaceInFile::new(
+                root.join("Notes.ini"),
+                r"\bStart\b",
+                "Finish",
+            ))]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn still_scans_a_file_whose_only_covering_change_is_narrowly_scoped() {
+        let root = fixture_dir("narrow_coverage");
+        fs::write(
+            root.join("StartGameModeBase.h"),
+            "START_API class FThing {};\n// part of the Start subsystem\n",
+        )
+        .unwrap();
+
+        // Mirrors replace_api_macro_in_header_file: narrowly rewrites only
+        // the _API macro, not every occurrence of the module name.
+        let already_covered = vec![Change::ReplaceInFile(ReplaceInFile::new(
+            root.join("StartGameModeBase.h"),
+            "START_API",
+            "FINISH_API",
+        ))];
+
+        let changes = scan_references(&root, "Start", "Finish", &already_covered);
+
+        assert_eq!(
+            changes,
+            vec![Change::ReplaceInFile(ReplaceInFile::new(
+                root.join("StartGameModeBase.h"),
+                r"\bStart\b",
+                "Finish",
+            ))]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn still_scans_a_file_whose_only_covering_change_quotes_old_name() {
+        let root = fixture_dir("quoted_coverage");
+        fs::write(
+            root.join("Dependent.Build.cs"),
+            "PublicDependencyModuleNames.AddRange(new string[] { \"Start\" });\n// Start must come before Other in load order\n",
+        )
+        .unwrap();
+
+        // Mirrors find_build_file_dependents: only rewrites quoted
+        // occurrences of the name, not the bare mention in the comment.
+        let already_covered = vec![Change::ReplaceInFile(ReplaceInFile::new(
+            root.join("Dependent.Build.cs"),
+            r#""Start""#,
+            r#""Finish""#,
+        ))];
+
+        let changes = scan_references(&root, "Start", "Finish", &already_covered);
+
+        assert_eq!(
+            changes,
+            vec![Change::ReplaceInFile(ReplaceInFile::new(
+                root.join("Dependent.Build.cs"),
+                r"\bStart\b",
+                "Finish",
+            ))]
+        );
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
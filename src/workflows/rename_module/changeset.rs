@@ -8,13 +8,29 @@ use walkdir::WalkDir;
 
 use crate::{
     changes::{AppendIniEntry, Change, RenameFile, ReplaceInFile},
-    unreal::Module,
+    naming::{validate_name, NameError},
+    unreal::{
+        plugin::{find_build_file_dependents, find_descriptor_module_entries, find_plugin_source_roots},
+        Module,
+    },
+    workflows::reference_scanner::scan_references,
 };
 
 use super::context::Context;
 
 /// Generate a changeset to rename an Unreal Engine module.
-pub fn generate_changeset(context: &Context) -> Vec<Change> {
+///
+/// Module discovery and dependency updates also cover modules living under
+/// `Plugins/<Name>/Source`: every `.Build.cs` across the project and its
+/// plugins that lists the module in `PublicDependencyModuleNames`/
+/// `PrivateDependencyModuleNames`, and every `.uplugin` `"Modules"` entry
+/// that names it, is updated alongside the module itself.
+///
+/// Returns a [`NameError`] if `old_name` or `new_name` is not a legal
+/// C++/UE identifier, if the project's `Source` directory cannot be read,
+/// or if the module's implementation file does not contain a recognizable
+/// `IMPLEMENT_MODULE` macro.
+pub fn generate_changeset(context: &Context) -> Result<Vec<Change>, NameError> {
     let Context {
         project_root,
         project_name,
@@ -25,13 +41,16 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
         target_name: new_name,
     } = context;
 
+    validate_name(old_name)?;
+    validate_name(new_name)?;
+
     let mut changeset = vec![
         rename_build_class(mod_root, old_name, new_name),
         rename_build_file(mod_root, old_name, new_name),
     ];
 
     if let Some(implementation_file) = find_mod_implementation(mod_root) {
-        update_mod_implementation(&mut changeset, implementation_file, new_name);
+        update_mod_implementation(&mut changeset, implementation_file, new_name)?;
     }
 
     changeset.extend(
@@ -40,17 +59,43 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
             .map(|header| replace_api_macro_in_header_file(mod_root, header, old_name, new_name)),
     );
 
+    // Computed up front, before scan_references runs, so these targeted
+    // changes can be passed to it as already covered. scan_references only
+    // skips a file whose covering change rewrites the bare old name; files
+    // touched solely by the quoted dependency/descriptor rewrites are still
+    // scanned for its generic \bOldName\b match.
+    let mut module_roots = vec![project_root.join("Source")];
+    module_roots.extend(find_plugin_source_roots(project_root));
+    let dependency_changes = find_build_file_dependents(&module_roots, old_name, new_name);
+    let descriptor_changes = find_descriptor_module_entries(project_root, old_name, new_name);
+
+    let mut covered = changeset.clone();
+    covered.extend(dependency_changes.iter().cloned());
+    covered.extend(descriptor_changes.iter().cloned());
+
+    // scan_references walks the whole project, including mod_root itself
+    // (e.g. every ModuleName.cpp's own `#include "ModuleName.h"`). Changes
+    // inside mod_root must run before the folder is renamed below, or they
+    // bake in a path that no longer exists by the time they're applied.
+    let (references_in_module, references_outside_module): (Vec<_>, Vec<_>) =
+        scan_references(project_root, old_name, new_name, &covered)
+            .into_iter()
+            .partition(|change| match change {
+                Change::ReplaceInFile(ReplaceInFile { path, .. }) => path.starts_with(mod_root),
+                _ => false,
+            });
+
+    changeset.extend(references_in_module);
+
     changeset.push(rename_source_subfolder(mod_root, new_name));
 
-    find_target_file_names(project_root)
-        .iter()
-        .for_each(|target_name| {
-            let target = project_root
-                .join("Source")
-                .join(target_name)
-                .with_extension("Target.cs");
-            changeset.push(replace_mod_reference_in_target(&target, old_name, new_name))
-        });
+    for target_name in find_target_file_names(project_root)? {
+        let target = project_root
+            .join("Source")
+            .join(target_name)
+            .with_extension("Target.cs");
+        changeset.push(replace_mod_reference_in_target(&target, old_name, new_name));
+    }
 
     changeset.push(replace_mod_reference_in_project_descriptor(
         project_root,
@@ -62,7 +107,11 @@ pub fn generate_changeset(context: &Context) -> Vec<Change> {
     changeset.push(update_existing_redirects(project_root, old_name, new_name));
     changeset.push(append_mod_redirect(project_root, old_name, new_name));
 
-    changeset
+    changeset.extend(dependency_changes);
+    changeset.extend(descriptor_changes);
+    changeset.extend(references_outside_module);
+
+    Ok(changeset)
 }
 
 fn find_mod_implementation(mod_root: &Path) -> Option<PathBuf> {
@@ -81,11 +130,17 @@ fn update_mod_implementation(
     changeset: &mut Vec<Change>,
     implementation_file: PathBuf,
     new_name: &str,
-) {
-    let content = fs::read_to_string(&implementation_file).unwrap();
+) -> Result<(), NameError> {
+    let content = fs::read_to_string(&implementation_file)
+        .map_err(|err| NameError::Io(err.to_string()))?;
     let regex =
         Regex::new(r#"(?P<macro>IMPLEMENT_(GAME_|PRIMARY_GAME_)?MODULE)\((?P<impl>.+?),"#).unwrap();
-    let captures = regex.captures(&content).unwrap();
+    let captures = regex.captures(&content).ok_or_else(|| {
+        NameError::Io(format!(
+            "{} does not contain an IMPLEMENT_MODULE macro",
+            implementation_file.display()
+        ))
+    })?;
     let macr = captures.name("macro").unwrap().as_str();
     let implementation = captures.name("impl").unwrap().as_str();
     changeset.push(Change::ReplaceInFile(ReplaceInFile::new(
@@ -99,7 +154,8 @@ fn update_mod_implementation(
         } else {
             format!(r#"_MODULE({}, {})"#, implementation, new_name)
         },
-    )))
+    )));
+    Ok(())
 }
 
 fn update_existing_redirects(project_root: &Path, old_name: &str, new_name: &str) -> Change {
@@ -186,9 +242,11 @@ fn rename_source_subfolder(mod_root: &Path, new_project_name: &str) -> Change {
     ))
 }
 
-fn find_target_file_names(project_root: &Path) -> Vec<String> {
-    fs::read_dir(project_root.join("Source"))
-        .expect("could not read source dir")
+fn find_target_file_names(project_root: &Path) -> Result<Vec<String>, NameError> {
+    let entries = fs::read_dir(project_root.join("Source"))
+        .map_err(|err| NameError::Io(format!("could not read source dir: {}", err)))?;
+
+    Ok(entries
         .filter_map(|entry| entry.ok())
         .filter_map(|entry| {
             entry
@@ -197,7 +255,7 @@ fn find_target_file_names(project_root: &Path) -> Vec<String> {
                 .and_then(|filename| filename.strip_suffix(".Target.cs"))
                 .map(|filename| filename.to_string())
         })
-        .collect()
+        .collect())
 }
 
 fn replace_mod_reference_in_project_descriptor(
@@ -2,8 +2,9 @@ use std::{fs, io::stdin, path::Path};
 
 use crate::{
     changes::{AppendIniEntry, Change, RenameFile, ReplaceInFile, SetIniEntry},
+    naming::{validate_name, NameError},
     presentation::log,
-    workflows::rename_project::context::Context,
+    workflows::{reference_scanner::scan_references, rename_project::context::Context},
 };
 
 use super::target::generate_target_changeset;
@@ -22,8 +23,14 @@ use super::target::generate_target_changeset;
 /// - Append redirect entry to DefaultEngine config file
 /// - Add a GameName entry under the URL section to the DefaultEngine.ini config file
 /// - Add a ProjectName entry under the GeneralProjectSettings section to the DefaultGame.ini config file
+/// - Replace any remaining reference to the old project name found anywhere in the project tree
 /// - Rename project root directory
-pub fn generate_code_changeset(context: &Context) -> Vec<Change> {
+///
+/// Returns a [`NameError`] if `old_project_name`, `new_project_name` or any
+/// requested target name is not a legal C++/UE identifier, or if the
+/// project's `Source` directory cannot be read. Callers are expected to
+/// surface the error through [`log`] rather than let it unwind.
+pub fn generate_code_changeset(context: &Context) -> Result<Vec<Change>, NameError> {
     let Context {
         project_root,
         project_name: old_project_name,
@@ -31,23 +38,23 @@ pub fn generate_code_changeset(context: &Context) -> Vec<Change> {
         ..
     } = context;
 
+    validate_name(old_project_name)?;
+    validate_name(new_project_name)?;
+
     let mut changeset = vec![];
 
     // @todo: introduce opt-out mechanism
     // do not need to rename all targets
-    // @todo: surface read errors
-    find_target_file_names(project_root)
-        .iter()
-        .for_each(|old_target_name| {
-            log::basic(format!("Found project target named {}.", old_target_name));
-            log::prompt("Target final name");
-            let new_target_name = request_final_target_name();
-            changeset.extend(generate_target_changeset(
-                old_target_name,
-                &new_target_name,
-                project_root,
-            ))
-        });
+    for old_target_name in find_target_file_names(project_root)? {
+        log::basic(format!("Found project target named {}.", old_target_name));
+        log::prompt("Target final name");
+        let new_target_name = request_final_target_name()?;
+        changeset.extend(generate_target_changeset(
+            &old_target_name,
+            &new_target_name,
+            project_root,
+        ))
+    }
 
     changeset.extend(vec![
         update_redirects_in_engine_config(project_root, new_project_name),
@@ -56,24 +63,36 @@ pub fn generate_code_changeset(context: &Context) -> Vec<Change> {
         add_project_name_to_game_config(project_root, new_project_name),
         replace_in_project_descriptor(project_root, old_project_name, new_project_name),
         rename_project_descriptor(project_root, old_project_name, new_project_name),
-        rename_project_root(project_root, new_project_name),
     ]);
 
-    changeset
+    let references = scan_references(
+        project_root,
+        old_project_name,
+        new_project_name,
+        &changeset,
+    );
+    changeset.extend(references);
+
+    changeset.push(rename_project_root(project_root, new_project_name));
+
+    Ok(changeset)
 }
 
-fn request_final_target_name() -> String {
+fn request_final_target_name() -> Result<String, NameError> {
     let mut buffer = String::new();
     stdin()
         .read_line(&mut buffer)
-        .map(|_| String::from(buffer.trim()))
-        .map_err(|err| err.to_string())
-        .unwrap()
+        .map_err(|err| NameError::Io(err.to_string()))?;
+    let name = buffer.trim().to_string();
+    validate_name(&name)?;
+    Ok(name)
 }
 
-fn find_target_file_names(project_root: &Path) -> Vec<String> {
-    fs::read_dir(project_root.join("Source"))
-        .expect("could not read source dir")
+fn find_target_file_names(project_root: &Path) -> Result<Vec<String>, NameError> {
+    let entries = fs::read_dir(project_root.join("Source"))
+        .map_err(|err| NameError::Io(format!("could not read source dir: {}", err)))?;
+
+    Ok(entries
         .filter_map(|entry| entry.ok())
         .filter_map(|entry| {
             entry
@@ -82,7 +101,7 @@ fn find_target_file_names(project_root: &Path) -> Vec<String> {
                 .and_then(|filename| filename.strip_suffix(".Target.cs"))
                 .map(|filename| filename.to_string())
         })
-        .collect()
+        .collect())
 }
 
 fn replace_in_project_descriptor(
@@ -168,7 +187,7 @@ fn rename_project_root(project_root: &Path, new_project_name: &str) -> Change {
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::fs;
 
     use crate::{
         changes::*,
@@ -177,107 +196,95 @@ mod tests {
 
     use super::generate_code_changeset;
 
+    /// Builds a project fixture under a temp directory with just enough on
+    /// disk for `generate_code_changeset` to run against a real
+    /// `scan_references` pass: the hardcoded descriptor/config files (with
+    /// content that does not itself contain the bare word "Start", so
+    /// `scan_references` doesn't double up on them), a `Source` directory
+    /// with no `.Target.cs` files (so `find_target_file_names` succeeds with zero targets
+    /// and the test never hits the interactive `request_final_target_name`
+    /// prompt), and one stray file outside the hardcoded list that
+    /// `scan_references` is expected to pick up on its own.
+    fn fixture_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("code_changeset_test_{}", name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("Source/Misc")).unwrap();
+        fs::create_dir_all(dir.join("Config")).unwrap();
+        fs::write(dir.join("Start.uproject"), "{}\n").unwrap();
+        fs::write(dir.join("Config/DefaultEngine.ini"), "[URL]\n").unwrap();
+        fs::write(dir.join("Config/DefaultGame.ini"), "[General]\n").unwrap();
+        fs::write(
+            dir.join("Source/Misc/Notes.ini"),
+            "GameDisplayName=Start\n",
+        )
+        .unwrap();
+        dir
+    }
+
     #[test]
     fn code_changeset_is_correct() {
+        let root = fixture_dir("code_changeset_is_correct");
+
         let changeset = generate_code_changeset(&Context {
-            project_root: PathBuf::from(""),
+            project_root: root.clone(),
             project_name: "Start".into(),
             project_type: ProjectType::Code,
             target_name: "Finish".into(),
-        });
+        })
+        .expect("changeset generation should succeed");
+
         let expected = vec![
-            // Replace old name with new name in project descriptor
-            Change::ReplaceInFile(ReplaceInFile::new("Start.uproject", "Start", "Finish")),
-            // Rename project descriptor
-            Change::RenameFile(RenameFile::new("Start.uproject", "Finish.uproject")),
-            // Replace old name with new name in executable target file
-            Change::ReplaceInFile(ReplaceInFile::new(
-                "Source/Start.Target.cs",
-                "Start",
-                "Finish",
-            )),
-            // Rename executable target file
-            Change::RenameFile(RenameFile::new(
-                "Source/Start.Target.cs",
-                "Source/Finish.Target.cs",
-            )),
-            // Replace old name with new name in editor target file
-            Change::ReplaceInFile(ReplaceInFile::new(
-                "Source/StartEditor.Target.cs",
-                "Start",
-                "Finish",
-            )),
-            // Rename editor target file
-            Change::RenameFile(RenameFile::new(
-                "Source/StartEditor.Target.cs",
-                "Source/FinishEditor.Target.cs",
-            )),
-            // Replace old name with new name in game module build file
-            Change::ReplaceInFile(ReplaceInFile::new(
-                "Source/Start/Start.Build.cs",
-                "Start",
-                "Finish",
-            )),
-            // Rename game module build file
-            Change::RenameFile(RenameFile::new(
-                "Source/Start/Start.Build.cs",
-                "Source/Start/Finish.Build.cs",
-            )),
-            // Replace old name with new name api references in header files
-            Change::ReplaceInFile(ReplaceInFile::new(
-                "Source/Start/StartGameModeBase.h",
-                "START_API",
-                "FINISH_API",
-            )),
-            // Rename game module header file
-            Change::RenameFile(RenameFile::new(
-                "Source/Start/Start.h",
-                "Source/Start/Finish.h",
-            )),
-            // Replace old name with new name api references in header files
-            Change::ReplaceInFile(ReplaceInFile::new(
-                "Source/Start/Start.cpp",
-                "Start",
-                "Finish",
-            )),
-            // Rename game module source file
-            Change::RenameFile(RenameFile::new(
-                "Source/Start/Start.cpp",
-                "Source/Start/Finish.cpp",
-            )),
-            // Rename source subfolder
-            Change::RenameFile(RenameFile::new("Source/Start", "Source/Finish")),
             // Update existing redirect entries in ini file
             Change::ReplaceInFile(ReplaceInFile::new(
-                "Config/DefaultEngine.ini",
+                root.join("Config/DefaultEngine.ini"),
                 r#"\(OldGameName="(?P<old>.+?)",\s*NewGameName=".+?"\)"#,
                 r#"(OldGameName="$old", NewGameName="/Script/Finish")"#,
             )),
             // Append redirect entry to ini file
             Change::AppendIniEntry(AppendIniEntry::new(
-                "Config/DefaultEngine.ini",
+                root.join("Config/DefaultEngine.ini"),
                 "/Script/Engine.Engine",
                 "+ActiveGameNameRedirects",
                 r#"(OldGameName="/Script/Start", NewGameName="/Script/Finish")"#,
             )),
             // Add Game Name entry to ini file
             Change::SetIniEntry(SetIniEntry::new(
-                "Config/DefaultEngine.ini",
+                root.join("Config/DefaultEngine.ini"),
                 "URL",
                 "GameName",
                 "Finish",
             )),
             // Add Project Name entry to ini file
             Change::SetIniEntry(SetIniEntry::new(
-                "Config/DefaultGame.ini",
+                root.join("Config/DefaultGame.ini"),
                 "/Script/EngineSettings.GeneralProjectSettings",
                 "ProjectName",
                 "Finish",
             )),
+            // Replace old name with new name in project descriptor
+            Change::ReplaceInFile(ReplaceInFile::new(
+                root.join("Start.uproject"),
+                "Start",
+                "Finish",
+            )),
+            // Rename project descriptor
+            Change::RenameFile(RenameFile::new(
+                root.join("Start.uproject"),
+                root.join("Finish.uproject"),
+            )),
+            // Stray reference picked up by scan_references, outside the
+            // hardcoded file list above
+            Change::ReplaceInFile(ReplaceInFile::new(
+                root.join("Source/Misc/Notes.ini"),
+                r"\bStart\b",
+                "Finish",
+            )),
             // Rename project root
-            Change::RenameFile(RenameFile::new("", "Finish")),
+            Change::RenameFile(RenameFile::new(root.clone(), root.with_file_name("Finish"))),
         ];
 
         assert_eq!(changeset, expected);
+
+        fs::remove_dir_all(&root).unwrap();
     }
 }
\ No newline at end of file